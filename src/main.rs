@@ -18,8 +18,14 @@ extern crate rand;
 #[macro_use]
 extern crate lazy_static;
 
+mod wordlist;
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::process;
+
 use clap::{AppSettings, Arg, ArgMatches};
-use rand::{Rng, sample, thread_rng};
+use rand::{OsRng, Rng, sample};
 
 
 /// Words to generate passwords from.
@@ -82,6 +88,111 @@ where
     sample(&mut rng, words.into_iter().map(AsRef::as_ref), length).join(separator)
 }
 
+/// Generate a single password as a random string of characters.
+///
+/// Use the random generator `rng` to draw `length` characters uniformly at random from
+/// `charset`, and concatenate them into a single password.
+pub fn generate_random_string<R: Rng>(rng: &mut R, charset: &str, length: usize) -> String {
+    let chars: Vec<char> = charset.chars().collect();
+    (0..length)
+        .map(|_| chars[rng.gen_range(0, chars.len())])
+        .collect()
+}
+
+/// Title-case every word in `password`, where words are separated by `separator`.
+///
+/// If `separator` is empty, `password` is treated as a single word.
+fn capitalize_words<R: Rng>(_rng: &mut R, password: String, separator: &str) -> String {
+    if separator.is_empty() {
+        capitalize(&password)
+    } else {
+        password
+            .split(separator)
+            .map(capitalize)
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+}
+
+/// Title-case a single word.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+/// Append `count` random digits to `password`.
+fn append_digits<R: Rng>(rng: &mut R, mut password: String, count: usize) -> String {
+    for _ in 0..count {
+        password.push((b'0' + rng.gen_range(0, 10) as u8) as char);
+    }
+    password
+}
+
+/// The character positions in `password` where `insert_symbol` could insert a symbol: one
+/// position per match of `separator`, or, if `separator` is empty or does not occur in
+/// `password`, every character gap (including the two ends).
+///
+/// `print_entropy_report` calls this on a representative placeholder password so its
+/// `--insert-symbol` position count uses the same boundary logic as `insert_symbol`.
+fn symbol_insertion_positions(password: &str, separator: &str) -> Vec<usize> {
+    let boundaries: Vec<usize> = if separator.is_empty() {
+        Vec::new()
+    } else {
+        password
+            .match_indices(separator)
+            .map(|(byte_index, _)| password[..byte_index].chars().count())
+            .collect()
+    };
+    if boundaries.is_empty() {
+        (0..=password.chars().count()).collect()
+    } else {
+        boundaries
+    }
+}
+
+/// Insert a random symbol from `symbols` at a random word boundary in `password`, where words
+/// are separated by `separator`.
+///
+/// Fall back to a random character position if `password` has no word boundaries.
+fn insert_symbol<R: Rng>(rng: &mut R, password: String, symbols: &str, separator: &str) -> String {
+    let symbol_chars: Vec<char> = symbols.chars().collect();
+    let symbol = symbol_chars[rng.gen_range(0, symbol_chars.len())];
+    let positions = symbol_insertion_positions(&password, separator);
+    let index = positions[rng.gen_range(0, positions.len())];
+    let mut chars: Vec<char> = password.chars().collect();
+    chars.insert(index, symbol);
+    chars.into_iter().collect()
+}
+
+arg_enum! {
+    /// Which set of characters to draw from in character mode.
+    #[derive(Clone, Copy, Debug)]
+    pub enum CharacterSet {
+        Alphanumeric,
+        Digits,
+        Symbols
+    }
+}
+
+impl CharacterSet {
+    /// The characters in this character set.
+    fn chars(self) -> &'static str {
+        match self {
+            CharacterSet::Alphanumeric => {
+                "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789"
+            }
+            CharacterSet::Digits => "0123456789",
+            CharacterSet::Symbols => {
+                "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789\
+                 !\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~"
+            }
+        }
+    }
+}
+
 
 static LICENSE: &'static str = "\
 wordlist license CC BY 3.0 US: <http://creativecommons.org/licenses/by/3.0/us/>.
@@ -107,29 +218,241 @@ fn get_words<'a>(list: ListOfWords) -> &'a Vec<&'static str> {
     }
 }
 
+/// Read a user-supplied wordlist from `path`, or from standard input if `path` is `-`.
+///
+/// Validate the wordlist with the same rules as the built-in wordlists, warn about any
+/// duplicate words, and require at least `length` words so passwords are never silently
+/// shorter than requested.
+fn read_wordlist(path: &str, length: usize) -> io::Result<Vec<String>> {
+    let mut contents = String::new();
+    if path == "-" {
+        io::stdin().read_to_string(&mut contents)?;
+    } else {
+        File::open(path)?.read_to_string(&mut contents)?;
+    }
+    let words: Vec<&str> = contents.lines().collect();
+    if let Err(error) = wordlist::validate_words(&words) {
+        eprintln!("error: invalid wordlist {}: {}", path, error);
+        process::exit(1);
+    }
+    if words.len() < length {
+        eprintln!(
+            "error: wordlist {} has only {} words, but --length {} needs at least that many",
+            path,
+            words.len(),
+            length
+        );
+        process::exit(1);
+    }
+    wordlist::warn_about_duplicates(&words);
+    Ok(words.into_iter().map(str::to_string).collect())
+}
+
+/// Where to draw words from to build a passphrase.
+enum WordSource {
+    /// One of the wordlists built into xkpwgen.
+    Builtin(ListOfWords),
+    /// A user-supplied wordlist, loaded from a file or from standard input.
+    Custom(Vec<String>),
+}
+
+/// How to generate a password.
+enum GenerationMode {
+    /// Draw words from a wordlist.
+    Words(WordSource),
+    /// Draw characters from a character set.
+    Chars(CharacterSet),
+}
+
 struct Options<'a> {
     length_of_password: usize,
     number_of_passwords: usize,
     word_separator: &'a str,
-    list_of_words: ListOfWords,
+    mode: GenerationMode,
+    show_stats: bool,
+    capitalize: bool,
+    append_digits: Option<usize>,
+    insert_symbol: bool,
+    symbol_set: &'a str,
 }
 
 impl<'a> Options<'a> {
     fn from_matches(matches: &'a ArgMatches<'a>) -> clap::Result<Options<'a>> {
         let length = value_t!(matches.value_of("length"), usize)?;
         let number = value_t!(matches.value_of("number"), usize)?;
-        let list_of_words = value_t!(matches.value_of("list_of_words"), ListOfWords)?;
         // Separator has a default value, so we can safely unwrap here!
         let separator = matches.value_of("separator").unwrap();
+        let mode = if matches.is_present("alphanumeric") {
+            GenerationMode::Chars(CharacterSet::Alphanumeric)
+        } else if matches.is_present("digits") {
+            GenerationMode::Chars(CharacterSet::Digits)
+        } else if matches.is_present("chars") {
+            GenerationMode::Chars(CharacterSet::Symbols)
+        } else if let Some(path) = matches.value_of("wordlist") {
+            let words = read_wordlist(path, length).unwrap_or_else(|error| {
+                eprintln!("error: failed to read wordlist {}: {}", path, error);
+                process::exit(1);
+            });
+            GenerationMode::Words(WordSource::Custom(words))
+        } else {
+            // `list_of_words` has no `default_value`, so that its presence doesn't trip the
+            // `conflicts_with_all` checks on --chars/--alphanumeric/--digits; default to `Slang`
+            // ourselves instead.
+            let list_of_words = match matches.value_of("list_of_words") {
+                Some(_) => value_t!(matches.value_of("list_of_words"), ListOfWords)?,
+                None => ListOfWords::Slang,
+            };
+            GenerationMode::Words(WordSource::Builtin(list_of_words))
+        };
+        let append_digits = match matches.value_of("append_digits") {
+            Some(raw) => Some(raw.parse::<usize>().unwrap_or_else(|_| {
+                eprintln!("error: --append-digits expects a non-negative number, got {}", raw);
+                process::exit(1);
+            })),
+            None => None,
+        };
+        let insert_symbol = matches.is_present("insert_symbol");
+        let symbol_set = matches.value_of("symbol_set").unwrap();
+        if insert_symbol && symbol_set.is_empty() {
+            eprintln!("error: --symbol-set must not be empty");
+            process::exit(1);
+        }
         Ok(Options {
             length_of_password: length,
             number_of_passwords: number,
             word_separator: separator,
-            list_of_words: list_of_words,
+            mode: mode,
+            show_stats: matches.is_present("stats"),
+            capitalize: matches.is_present("capitalize"),
+            append_digits: append_digits,
+            insert_symbol: insert_symbol,
+            symbol_set: symbol_set,
         })
     }
 }
 
+/// The entropy, in bits, of `length` words drawn from a wordlist with the given `stats`, computed
+/// as `length * log2(number_of_words)`, along with a human-readable description of the wordlist.
+///
+/// This counts every word as an independent uniform draw; `generate_password` never repeats a
+/// word within one password, so its passwords carry somewhat less entropy than this.
+fn word_entropy(stats: &wordlist::WordlistStatistics, length: usize) -> (f64, String) {
+    let entropy = length as f64 * (stats.number_of_words as f64).log2();
+    let description = format!(
+        "{} words, {} to {} characters long, {} words per password",
+        stats.number_of_words,
+        stats.min_word_length,
+        stats.max_word_length,
+        length
+    );
+    (entropy, description)
+}
+
+/// The Shannon entropy, in bits, of passwords drawn from `charset`, along with a human-readable
+/// description of the character set.
+fn charset_entropy(charset: &str, length: usize) -> (f64, String) {
+    let charset_size = charset.chars().count();
+    let entropy = length as f64 * (charset_size as f64).log2();
+    let description = format!(
+        "{} possible characters, {} characters per password",
+        charset_size,
+        length
+    );
+    (entropy, description)
+}
+
+/// The number of positions `--insert-symbol` could draw from for a password generated from
+/// `options`, whose wordlist statistics are `word_stats` (`None` in character mode).
+///
+/// Builds a placeholder password of the same shape (number of words and, for word mode, longest
+/// word length, plus any `--append-digits` digits) that `options` would actually generate, and
+/// runs it through `symbol_insertion_positions`, the same boundary logic `insert_symbol` uses at
+/// runtime. Reusing that logic (rather than re-deriving the position count algebraically from
+/// `length`) keeps this in sync with `insert_symbol`, including its single-word fallback to
+/// character gaps.
+fn symbol_insertion_position_count(
+    options: &Options,
+    word_stats: Option<&wordlist::WordlistStatistics>,
+) -> usize {
+    let filler = filler_char_not_in(options.word_separator);
+    let mut placeholder: String = match word_stats {
+        Some(stats) => placeholder_password(stats, options),
+        None => ::std::iter::repeat(filler).take(options.length_of_password).collect(),
+    };
+    // `--append-digits` runs before `--insert-symbol`, so its digits lengthen the password too.
+    placeholder.extend(::std::iter::repeat(filler).take(options.append_digits.unwrap_or(0)));
+    symbol_insertion_positions(&placeholder, options.word_separator).len()
+}
+
+/// Build a placeholder password of `options.length_of_password` words, each as long as the
+/// longest word described by `stats`, joined by `options.word_separator`.
+///
+/// The longest word is a conservative stand-in for a real word: as long as no real word contains
+/// the separator, it can only over-count, never under-count, the symbol positions a real
+/// generated password would offer. The filler character
+/// is chosen to not occur in the separator itself, so it can't be mistaken for a word boundary.
+fn placeholder_password(stats: &wordlist::WordlistStatistics, options: &Options) -> String {
+    let filler = filler_char_not_in(options.word_separator);
+    let placeholder_word: String = ::std::iter::repeat(filler).take(stats.max_word_length).collect();
+    vec![placeholder_word; options.length_of_password].join(options.word_separator)
+}
+
+/// A character that does not occur in `separator`, for building placeholder words that can't be
+/// mistaken for a word boundary.
+fn filler_char_not_in(separator: &str) -> char {
+    ('a'..='z')
+        .chain('0'..='9')
+        .find(|c| !separator.contains(*c))
+        .unwrap_or('\u{1F600}')
+}
+
+/// Print an entropy report for `options`, accounting for the base wordlist/charset entropy as
+/// well as any entropy added by the `--append-digits` and `--insert-symbol` transforms.
+fn print_entropy_report(options: &Options) {
+    let (mut entropy, description, word_stats) = match options.mode {
+        GenerationMode::Words(WordSource::Builtin(list_of_words)) => {
+            let stats = wordlist::WordlistStatistics::from_words(get_words(list_of_words).to_vec());
+            let (entropy, description) = word_entropy(&stats, options.length_of_password);
+            (entropy, description, Some(stats))
+        }
+        GenerationMode::Words(WordSource::Custom(ref words)) => {
+            let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+            let stats = wordlist::WordlistStatistics::from_words(words);
+            let (entropy, description) = word_entropy(&stats, options.length_of_password);
+            (entropy, description, Some(stats))
+        }
+        GenerationMode::Chars(charset) => {
+            let (entropy, description) =
+                charset_entropy(charset.chars(), options.length_of_password);
+            (entropy, description, None)
+        }
+    };
+    println!("Entropy: {:.1} bits ({})", entropy, description);
+
+    if let Some(count) = options.append_digits {
+        let extra = count as f64 * 10f64.log2();
+        entropy += extra;
+        println!("  + {:.1} bits from --append-digits {}", extra, count);
+    }
+
+    if options.insert_symbol {
+        let symbol_count = options.symbol_set.chars().count();
+        let position_count = symbol_insertion_position_count(options, word_stats.as_ref());
+        let extra = (symbol_count as f64).log2() + (position_count as f64).log2();
+        entropy += extra;
+        println!(
+            "  + {:.1} bits from --insert-symbol ({} possible symbols, {} possible positions)",
+            extra,
+            symbol_count,
+            position_count
+        );
+    }
+
+    if options.append_digits.is_some() || options.insert_symbol {
+        println!("Total entropy: {:.1} bits", entropy);
+    }
+}
+
 fn main() {
     let long_version = format!(
         "{}\n
@@ -166,15 +489,76 @@ wordlist copyright (C) 2016 EFF <https://www.eff.org/copyright>",
                 .short("l")
                 .long("length")
                 .default_value("4")
-                .help("The number of words in each password"),
+                .help("The number of words (or, with --chars, characters) in each password"),
         )
         .arg(
             Arg::with_name("list_of_words")
                 .short("w")
                 .long("--words")
                 .possible_values(&ListOfWords::variants())
-                .default_value(ListOfWords::variants()[0])
-                .help("The list of words to use to generate a password"),
+                // No `default_value`, so `is_present` (and hence this conflict check) only
+                // trips when the user actually passes `-w`/`--words` themselves.
+                .conflicts_with_all(&["chars", "alphanumeric", "digits"])
+                .help("The list of words to use to generate a password (default: slang)"),
+        )
+        .arg(
+            Arg::with_name("wordlist")
+                .long("wordlist")
+                .takes_value(true)
+                .value_name("PATH")
+                .conflicts_with_all(&["chars", "alphanumeric", "digits"])
+                .help("Read a custom wordlist from PATH, or from stdin if PATH is -; overrides --words"),
+        )
+        .arg(
+            Arg::with_name("chars")
+                .short("c")
+                .long("chars")
+                .conflicts_with_all(&["alphanumeric", "digits"])
+                .help("Generate a random string of printable ASCII characters instead of a passphrase"),
+        )
+        .arg(
+            Arg::with_name("alphanumeric")
+                .short("a")
+                .long("alphanumeric")
+                .conflicts_with_all(&["chars", "digits"])
+                .help("Generate a random alphanumeric string [A-Za-z0-9] instead of a passphrase"),
+        )
+        .arg(
+            Arg::with_name("digits")
+                .short("d")
+                .long("digits")
+                .conflicts_with_all(&["chars", "alphanumeric"])
+                .help("Generate a random string of digits instead of a passphrase"),
+        )
+        .arg(
+            Arg::with_name("stats")
+                .long("stats")
+                .alias("entropy")
+                .help("Print the entropy of the generated passwords, along with wordlist statistics"),
+        )
+        .arg(
+            Arg::with_name("capitalize")
+                .long("capitalize")
+                .help("Title-case each word in the generated passphrase"),
+        )
+        .arg(
+            Arg::with_name("append_digits")
+                .long("append-digits")
+                .takes_value(true)
+                .value_name("N")
+                .help("Append N random digits to each generated password"),
+        )
+        .arg(
+            Arg::with_name("insert_symbol")
+                .long("insert-symbol")
+                .help("Insert a random symbol from --symbol-set at a random word boundary"),
+        )
+        .arg(
+            Arg::with_name("symbol_set")
+                .long("symbol-set")
+                .takes_value(true)
+                .default_value("!@#$%^&*()-_=+")
+                .help("The symbols to draw from for --insert-symbol"),
         )
         .settings(
             &[
@@ -187,13 +571,109 @@ wordlist copyright (C) 2016 EFF <https://www.eff.org/copyright>",
 
     let options = Options::from_matches(&matches).unwrap_or_else(|e| e.exit());
 
-    for _ in 0..options.number_of_passwords {
-        let password = generate_password(
-            &mut thread_rng(),
-            get_words(options.list_of_words),
-            options.length_of_password,
-            options.word_separator,
+    if options.show_stats {
+        print_entropy_report(&options);
+    }
+
+    let mut rng = OsRng::new().unwrap_or_else(|error| {
+        eprintln!(
+            "error: failed to access the operating system's random number generator: {}",
+            error
         );
+        process::exit(1);
+    });
+    for _ in 0..options.number_of_passwords {
+        let mut password = match options.mode {
+            GenerationMode::Words(WordSource::Builtin(list_of_words)) => generate_password(
+                &mut rng,
+                get_words(list_of_words),
+                options.length_of_password,
+                options.word_separator,
+            ),
+            GenerationMode::Words(WordSource::Custom(ref words)) => generate_password(
+                &mut rng,
+                words,
+                options.length_of_password,
+                options.word_separator,
+            ),
+            GenerationMode::Chars(charset) => {
+                generate_random_string(&mut rng, charset.chars(), options.length_of_password)
+            }
+        };
+        if options.capitalize {
+            password = capitalize_words(&mut rng, password, options.word_separator);
+        }
+        if let Some(count) = options.append_digits {
+            password = append_digits(&mut rng, password, count);
+        }
+        if options.insert_symbol {
+            password = insert_symbol(&mut rng, password, options.symbol_set, options.word_separator);
+        }
         println!("{}", password);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{append_digits, capitalize_words, filler_char_not_in, generate_password,
+                generate_random_string, insert_symbol, symbol_insertion_positions};
+    use rand::{SeedableRng, XorShiftRng};
+
+    fn seeded_rng() -> XorShiftRng {
+        XorShiftRng::from_seed([1, 2, 3, 4])
+    }
+
+    #[test]
+    fn generate_password_is_deterministic_with_a_seeded_rng() {
+        let words = vec!["alfa", "bravo", "charlie", "delta"];
+        let first = generate_password(&mut seeded_rng(), &words, 2, " ");
+        let second = generate_password(&mut seeded_rng(), &words, 2, " ");
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn generate_random_string_is_deterministic_with_a_seeded_rng() {
+        let first = generate_random_string(&mut seeded_rng(), "0123456789", 10);
+        let second = generate_random_string(&mut seeded_rng(), "0123456789", 10);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn capitalize_words_title_cases_every_word() {
+        let password = capitalize_words(&mut seeded_rng(), "alfa bravo charlie".to_string(), " ");
+        assert_eq!(password, "Alfa Bravo Charlie");
+    }
+
+    #[test]
+    fn append_digits_appends_the_requested_number_of_digits() {
+        let password = append_digits(&mut seeded_rng(), "alfa bravo".to_string(), 3);
+        assert_eq!(password.len(), "alfa bravo".len() + 3);
+        assert!(password[password.len() - 3..].chars().all(|c| c.is_digit(10)));
+    }
+
+    #[test]
+    fn insert_symbol_inserts_exactly_one_symbol_from_the_given_set() {
+        let symbols = "!@#";
+        let password = insert_symbol(&mut seeded_rng(), "alfa bravo".to_string(), symbols, " ");
+        assert_eq!(password.chars().count(), "alfa bravo".chars().count() + 1);
+        assert_eq!(password.chars().filter(|c| symbols.contains(*c)).count(), 1);
+    }
+
+    #[test]
+    fn symbol_insertion_positions_uses_word_boundaries_when_the_separator_occurs() {
+        let positions = symbol_insertion_positions("alfa bravo charlie", " ");
+        assert_eq!(positions, vec![4, 10]);
+    }
+
+    #[test]
+    fn symbol_insertion_positions_falls_back_to_every_character_gap_for_a_single_word() {
+        let positions = symbol_insertion_positions("alfa", " ");
+        assert_eq!(positions, (0..="alfa".len()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn filler_char_not_in_avoids_the_separator() {
+        assert_ne!(filler_char_not_in("x"), 'x');
+        assert_eq!(filler_char_not_in("abcdefghijklmnopqrstuvwxyz0123456789"), '\u{1F600}');
+    }
+}
@@ -26,6 +26,67 @@ pub fn builtin_words() -> Vec<&'static str> {
     EFF_WORDLIST.lines().collect()
 }
 
+use std::fmt;
+
+/// An error in a user-supplied wordlist.
+#[derive(Debug)]
+pub enum WordlistError {
+    /// The wordlist has no words at all.
+    Empty,
+    /// Line `.0` (1-based) is empty.
+    EmptyLine(usize),
+    /// Line `.0` (1-based) contains the whitespace-containing word `.1`.
+    WordContainsWhitespace(usize, String),
+}
+
+impl fmt::Display for WordlistError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            WordlistError::Empty => write!(f, "wordlist is empty"),
+            WordlistError::EmptyLine(line) => write!(f, "line {}: empty line", line),
+            WordlistError::WordContainsWhitespace(line, ref word) => {
+                write!(f, "line {}: word {:?} contains whitespace", line, word)
+            }
+        }
+    }
+}
+
+impl ::std::error::Error for WordlistError {
+    fn description(&self) -> &str {
+        "invalid wordlist"
+    }
+}
+
+/// Validate a user-supplied wordlist.
+///
+/// Apply the same rules the built-in wordlists are held to: at least one word, no empty lines,
+/// and no words containing whitespace.
+pub fn validate_words(words: &[&str]) -> Result<(), WordlistError> {
+    if words.is_empty() {
+        return Err(WordlistError::Empty);
+    }
+    for (index, word) in words.iter().enumerate() {
+        if word.is_empty() {
+            return Err(WordlistError::EmptyLine(index + 1));
+        }
+        if word.contains(|c: char| c.is_whitespace()) {
+            return Err(WordlistError::WordContainsWhitespace(index + 1, (*word).to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// Warn on stderr about every duplicate word in `words`.
+pub fn warn_about_duplicates(words: &[&str]) {
+    use std::collections::HashSet;
+    let mut seen_words = HashSet::with_capacity(words.len());
+    for word in words {
+        if !seen_words.insert(*word) {
+            eprintln!("warning: duplicate word in wordlist: {}", word);
+        }
+    }
+}
+
 pub struct WordlistStatistics {
     pub number_of_words: usize,
     pub min_word_length: usize,
@@ -45,7 +106,7 @@ impl WordlistStatistics {
 
 #[cfg(test)]
 mod test {
-    use super::builtin_words;
+    use super::{WordlistError, builtin_words, validate_words};
     use std::collections::HashSet;
 
     #[test]
@@ -86,4 +147,12 @@ mod test {
                     word);
         }
     }
+
+    #[test]
+    fn validate_words_rejects_an_empty_wordlist() {
+        match validate_words(&[]) {
+            Err(WordlistError::Empty) => (),
+            other => panic!("Expected WordlistError::Empty, got {:?}", other),
+        }
+    }
 }
@@ -17,7 +17,8 @@
 
 use std::collections::HashSet;
 use std::ffi::OsStr;
-use std::process::{Command, ExitStatus, Output};
+use std::io::Write;
+use std::process::{Command, ExitStatus, Output, Stdio};
 
 static EFF_WORDLIST: &'static str = include_str!(concat!(env!("OUT_DIR"), "/eff_wordlist.txt"));
 
@@ -62,10 +63,60 @@ stderr:
     result
 }
 
+fn assert_run_with_stdin<S: AsRef<OsStr>>(args: &[S], stdin: &str) -> Result {
+    let result = run_with_stdin(args, stdin);
+    assert!(result.status.success(),
+            "xkpwgen failed with output:
+stdout:
+{}
+stderr:
+{}
+",
+            result.stdout,
+            result.stderr);
+    result
+}
+
 fn all_words<'a>(s: &'a str, sep: &str) -> Vec<&'a str> {
     s.lines().flat_map(|w| w.split(sep)).collect()
 }
 
+fn run_with_stdin<S: AsRef<OsStr>>(args: &[S], stdin: &str) -> Result {
+    let mut child = Command::new("cargo")
+        .arg("run")
+        .arg("--quiet")
+        .arg("--")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to run xkpwgen for testing");
+    child
+        .stdin
+        .take()
+        .expect("Failed to open stdin")
+        .write_all(stdin.as_bytes())
+        .expect("Failed to write to stdin");
+    child
+        .wait_with_output()
+        .expect("Failed to wait for xkpwgen")
+        .into()
+}
+
+/// Write `words` as a newline-separated wordlist to a temporary file named after `name`, and
+/// return its path.
+fn write_wordlist(name: &str, words: &[&str]) -> String {
+    let path = std::env::temp_dir().join(format!("xkpwgen-test-wordlist-{}.txt", name));
+    let mut file = std::fs::File::create(&path).expect("Failed to create temporary wordlist");
+    if !words.is_empty() {
+        writeln!(file, "{}", words.join("\n")).expect("Failed to write temporary wordlist");
+    }
+    path.to_str()
+        .expect("Temporary wordlist path is not valid UTF-8")
+        .to_string()
+}
+
 macro_rules! repeat_run {
     ($result:ident, $command:expr, $body:block) => {
         {
@@ -205,3 +256,125 @@ fn it_has_no_word_with_space_in_the_wordlist() {
                 word);
     }
 }
+
+#[test]
+fn it_reads_a_custom_wordlist_from_a_file() {
+    let words = ["alfa", "bravo", "charlie", "delta", "echo"];
+    let path = write_wordlist("from-file", &words);
+    let result = assert_run(&["--wordlist", &path, "-l", "3", "-n", "1"]);
+    for word in all_words(&result.stdout, " ") {
+        assert!(words.contains(&word), "Word {} not in custom wordlist!", word);
+    }
+}
+
+#[test]
+fn it_reads_a_custom_wordlist_from_stdin() {
+    let words = "alfa\nbravo\ncharlie\ndelta\necho";
+    let result = assert_run_with_stdin(&["--wordlist", "-", "-l", "3", "-n", "1"], words);
+    for word in all_words(&result.stdout, " ") {
+        assert!(words.lines().any(|w| w == word),
+                "Word {} not in custom wordlist!",
+                word);
+    }
+}
+
+#[test]
+fn it_rejects_an_empty_custom_wordlist() {
+    let path = write_wordlist("empty", &[]);
+    let result = run(&["--wordlist", &path]);
+    assert!(!result.status.success(), "Expected failure, got: {:?}", result.stdout);
+    assert!(result.stderr.contains("empty"),
+            "Expected an error about an empty wordlist, got:
+{}",
+            result.stderr);
+}
+
+#[test]
+fn it_rejects_a_custom_wordlist_shorter_than_the_requested_length() {
+    let words = ["alfa", "bravo"];
+    let path = write_wordlist("too-short", &words);
+    let result = run(&["--wordlist", &path, "-l", "8"]);
+    assert!(!result.status.success(), "Expected failure, got: {:?}", result.stdout);
+}
+
+#[test]
+fn it_prints_entropy_stats_for_word_mode() {
+    // 4 words, 2 per password: log2(4) * 2 = 4.0 bits exactly.
+    let words = ["alfa", "bravo", "charlie", "delta"];
+    let path = write_wordlist("stats-word-mode", &words);
+    let result = assert_run(&["--wordlist", &path, "--stats", "-l", "2", "-n", "1"]);
+    assert!(result.stdout.contains("Entropy: 4.0 bits (4 words"),
+            "Expected an entropy report of exactly 4.0 bits, got:
+{}",
+            result.stdout);
+}
+
+#[test]
+fn it_prints_entropy_stats_for_char_mode() {
+    // The digit charset always has 10 characters: log2(10) * 5 ~= 16.6 bits.
+    let result = assert_run(&["--stats", "--digits", "-l", "5", "-n", "1"]);
+    assert!(result.stdout.contains("Entropy: 16.6 bits (10 possible characters"),
+            "Expected an entropy report of exactly 16.6 bits, got:
+{}",
+            result.stdout);
+}
+
+#[test]
+fn it_accounts_for_every_character_gap_when_insert_symbol_has_no_word_boundary() {
+    // A single word has no separator to insert at, so `insert_symbol` falls back to every
+    // character gap in the word, not just `length - 1` word boundaries. With every word the
+    // same length, the report's placeholder word matches the real one exactly, so the reported
+    // position count must be the word length (4) + 1 = 5 possible positions.
+    let words = ["abcd", "efgh", "ijkl", "mnop"];
+    let path = write_wordlist("stats-insert-symbol-single-word", &words);
+    let result = assert_run(
+        &[
+            "--wordlist",
+            &path,
+            "--length",
+            "1",
+            "--insert-symbol",
+            "--symbol-set",
+            "!",
+            "--stats",
+            "-n",
+            "1",
+        ],
+    );
+    assert!(
+        result
+            .stdout
+            .contains("possible symbols, 5 possible positions)"),
+        "Expected exactly 5 possible --insert-symbol positions, got:
+{}",
+        result.stdout
+    );
+}
+
+#[test]
+fn it_generates_alphanumeric_passwords_with_the_alphanumeric_flag() {
+    repeat_run!(result, &["-a", "-l", "20"], {
+        for password in result.stdout.lines() {
+            assert!(password.chars().all(|c| c.is_alphanumeric()),
+                    "Password {} is not alphanumeric!",
+                    password);
+        }
+    })
+}
+
+#[test]
+fn it_generates_digit_passwords_with_the_digits_flag() {
+    repeat_run!(result, &["-d", "-l", "20"], {
+        for password in result.stdout.lines() {
+            assert!(password.chars().all(|c| c.is_digit(10)),
+                    "Password {} is not all digits!",
+                    password);
+        }
+    })
+}
+
+#[test]
+fn it_rejects_combining_alphanumeric_and_digits() {
+    let result = run(&["-a", "-d"]);
+    assert!(!result.status.success(), "Expected -a and -d to conflict");
+}